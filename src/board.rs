@@ -0,0 +1,562 @@
+//! An 8x8 board model that lets [`crate::pgn::Pgn`] replay a game's SAN
+//! moves into concrete positions, instead of stopping at the movetext
+//! text itself.
+//!
+//! Move legality here is geometric only (piece movement rules, blocking
+//! pieces, en passant, castling rights) — it does not detect whether a
+//! move leaves its own king in check, since the parser only needs enough
+//! rule-checking to disambiguate SAN, not to referee a legal game.
+
+use thiserror::Error;
+
+use crate::pgn::Color;
+
+#[derive(Debug, Error)]
+pub enum BoardError {
+    #[error("invalid FEN: {0}")]
+    InvalidFen(String),
+    #[error("{0}")]
+    IllegalMove(String),
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Square(u8);
+
+impl Square {
+    pub fn new(file: u8, rank: u8) -> Self {
+        Self(rank * 8 + file)
+    }
+
+    pub fn file(self) -> u8 {
+        self.0 % 8
+    }
+
+    pub fn rank(self) -> u8 {
+        self.0 / 8
+    }
+
+    pub fn from_algebraic(s: &str) -> Option<Self> {
+        let mut chars = s.chars();
+        let file = chars.next()?;
+        let rank = chars.next()?;
+        if chars.next().is_some() || !('a'..='h').contains(&file) || !('1'..='8').contains(&rank) {
+            return None;
+        }
+        Some(Self::new(file as u8 - b'a', rank as u8 - b'1'))
+    }
+}
+
+impl std::fmt::Display for Square {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}{}", (b'a' + self.file()) as char, self.rank() + 1)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Piece {
+    Pawn,
+    Knight,
+    Bishop,
+    Rook,
+    Queen,
+    King,
+}
+
+impl Piece {
+    fn from_letter(c: char) -> Option<Self> {
+        match c {
+            'N' => Some(Piece::Knight),
+            'B' => Some(Piece::Bishop),
+            'R' => Some(Piece::Rook),
+            'Q' => Some(Piece::Queen),
+            'K' => Some(Piece::King),
+            _ => None,
+        }
+    }
+
+    fn from_fen_char(c: char) -> Option<(Color, Self)> {
+        let color = if c.is_ascii_uppercase() {
+            Color::White
+        } else {
+            Color::Black
+        };
+        let piece = match c.to_ascii_lowercase() {
+            'p' => Piece::Pawn,
+            'n' => Piece::Knight,
+            'b' => Piece::Bishop,
+            'r' => Piece::Rook,
+            'q' => Piece::Queen,
+            'k' => Piece::King,
+            _ => return None,
+        };
+        Some((color, piece))
+    }
+
+    fn to_fen_char(self, color: Color) -> char {
+        let c = match self {
+            Piece::Pawn => 'p',
+            Piece::Knight => 'n',
+            Piece::Bishop => 'b',
+            Piece::Rook => 'r',
+            Piece::Queen => 'q',
+            Piece::King => 'k',
+        };
+        match color {
+            Color::White => c.to_ascii_uppercase(),
+            Color::Black => c,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct CastlingRights {
+    pub white_kingside: bool,
+    pub white_queenside: bool,
+    pub black_kingside: bool,
+    pub black_queenside: bool,
+}
+
+/// A chess position: piece placement, side to move, castling rights and
+/// the en passant target square, mirroring the fields of a FEN record.
+#[derive(Debug, Clone)]
+pub struct Position {
+    board: [Option<(Color, Piece)>; 64],
+    side_to_move: Color,
+    castling: CastlingRights,
+    en_passant: Option<Square>,
+}
+
+const STARTPOS_FEN: &str = "rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w KQkq - 0 1";
+
+impl Position {
+    pub fn start() -> Self {
+        Self::from_fen(STARTPOS_FEN).expect("the standard starting FEN is always valid")
+    }
+
+    pub fn from_fen(fen: &str) -> Result<Self, BoardError> {
+        let mut fields = fen.split_whitespace();
+        let placement = fields
+            .next()
+            .ok_or_else(|| BoardError::InvalidFen(fen.to_string()))?;
+        let side = fields.next().unwrap_or("w");
+        let castling_field = fields.next().unwrap_or("-");
+        let en_passant_field = fields.next().unwrap_or("-");
+
+        let ranks: Vec<&str> = placement.split('/').collect();
+        if ranks.len() != 8 {
+            return Err(BoardError::InvalidFen(fen.to_string()));
+        }
+
+        let mut board = [None; 64];
+        for (rank_from_top, rank_str) in ranks.iter().enumerate() {
+            let rank = 7 - rank_from_top as u8;
+            let mut file = 0u8;
+            for c in rank_str.chars() {
+                if let Some(skip) = c.to_digit(10) {
+                    file += skip as u8;
+                } else {
+                    if file > 7 {
+                        return Err(BoardError::InvalidFen(fen.to_string()));
+                    }
+                    let (color, piece) = Piece::from_fen_char(c)
+                        .ok_or_else(|| BoardError::InvalidFen(fen.to_string()))?;
+                    board[Square::new(file, rank).0 as usize] = Some((color, piece));
+                    file += 1;
+                }
+            }
+        }
+
+        let side_to_move = match side {
+            "w" => Color::White,
+            "b" => Color::Black,
+            _ => return Err(BoardError::InvalidFen(fen.to_string())),
+        };
+
+        let castling = CastlingRights {
+            white_kingside: castling_field.contains('K'),
+            white_queenside: castling_field.contains('Q'),
+            black_kingside: castling_field.contains('k'),
+            black_queenside: castling_field.contains('q'),
+        };
+
+        let en_passant = if en_passant_field == "-" {
+            None
+        } else {
+            Some(
+                Square::from_algebraic(en_passant_field)
+                    .ok_or_else(|| BoardError::InvalidFen(fen.to_string()))?,
+            )
+        };
+
+        Ok(Self {
+            board,
+            side_to_move,
+            castling,
+            en_passant,
+        })
+    }
+
+    /// Renders this position back to FEN. The halfmove clock and fullmove
+    /// number aren't tracked, so they're always written as `0 1`.
+    pub fn to_fen(&self) -> String {
+        let mut placement = String::new();
+        for rank_from_top in 0..8u8 {
+            let rank = 7 - rank_from_top;
+            let mut empty = 0u8;
+            for file in 0..8u8 {
+                match self.board[Square::new(file, rank).0 as usize] {
+                    Some((color, piece)) => {
+                        if empty > 0 {
+                            placement.push_str(&empty.to_string());
+                            empty = 0;
+                        }
+                        placement.push(piece.to_fen_char(color));
+                    }
+                    None => empty += 1,
+                }
+            }
+            if empty > 0 {
+                placement.push_str(&empty.to_string());
+            }
+            if rank_from_top != 7 {
+                placement.push('/');
+            }
+        }
+
+        let side = match self.side_to_move {
+            Color::White => "w",
+            Color::Black => "b",
+        };
+
+        let mut castling = String::new();
+        if self.castling.white_kingside {
+            castling.push('K');
+        }
+        if self.castling.white_queenside {
+            castling.push('Q');
+        }
+        if self.castling.black_kingside {
+            castling.push('k');
+        }
+        if self.castling.black_queenside {
+            castling.push('q');
+        }
+        if castling.is_empty() {
+            castling.push('-');
+        }
+
+        let en_passant = self
+            .en_passant
+            .map(|square| square.to_string())
+            .unwrap_or_else(|| "-".to_string());
+
+        format!("{placement} {side} {castling} {en_passant} 0 1")
+    }
+
+    /// Applies a SAN move (e.g. `Nf3`, `exd5`, `e8=Q`, `O-O`) to this
+    /// position, resolving the source square from the piece's pseudo-legal
+    /// moves and any file/rank disambiguation in the SAN itself.
+    pub fn apply_san(&mut self, san: &str) -> Result<(), BoardError> {
+        let san = san.trim_end_matches(['+', '#']);
+
+        if san == "O-O" || san == "0-0" {
+            return self.castle(true);
+        }
+        if san == "O-O-O" || san == "0-0-0" {
+            return self.castle(false);
+        }
+
+        let (san, promotion) = match san.split_once('=') {
+            Some((base, promo)) => {
+                let promoted = Piece::from_letter(promo.chars().next().unwrap_or('Q'))
+                    .ok_or_else(|| BoardError::IllegalMove(format!("bad promotion in {san}")))?;
+                (base, Some(promoted))
+            }
+            None => (san, None),
+        };
+
+        let mut rest = san;
+        let piece = match rest.chars().next() {
+            Some(letter @ ('N' | 'B' | 'R' | 'Q' | 'K')) => {
+                rest = &rest[1..];
+                Piece::from_letter(letter).unwrap()
+            }
+            _ => Piece::Pawn,
+        };
+
+        let capture = rest.contains('x');
+        let rest: String = rest.chars().filter(|&c| c != 'x').collect();
+
+        if rest.len() < 2 {
+            return Err(BoardError::IllegalMove(format!(
+                "couldn't read a destination square from {san}"
+            )));
+        }
+        let dest = Square::from_algebraic(&rest[rest.len() - 2..])
+            .ok_or_else(|| BoardError::IllegalMove(format!("bad destination square in {san}")))?;
+        let disambiguation = &rest[..rest.len() - 2];
+
+        let source = self.find_source(piece, dest, disambiguation, capture)?;
+        self.make_move(source, dest, piece, promotion, capture);
+        Ok(())
+    }
+
+    fn find_source(
+        &self,
+        piece: Piece,
+        dest: Square,
+        disambiguation: &str,
+        capture: bool,
+    ) -> Result<Square, BoardError> {
+        let file_hint = disambiguation.chars().find(|c| c.is_ascii_lowercase());
+        let rank_hint = disambiguation.chars().find(|c| c.is_ascii_digit());
+
+        let candidates: Vec<Square> = (0..64u8)
+            .map(Square)
+            .filter(|&square| {
+                matches!(self.board[square.0 as usize], Some((color, p)) if color == self.side_to_move && p == piece)
+            })
+            .filter(|square| file_hint.is_none_or(|f| square.file() == f as u8 - b'a'))
+            .filter(|square| rank_hint.is_none_or(|r| square.rank() == r as u8 - b'1'))
+            .filter(|&square| self.can_reach(piece, square, dest, capture))
+            .collect();
+
+        match candidates.as_slice() {
+            [square] => Ok(*square),
+            [] => Err(BoardError::IllegalMove(format!(
+                "no {piece:?} can reach {dest}"
+            ))),
+            _ => Err(BoardError::IllegalMove(format!(
+                "ambiguous move to {dest}"
+            ))),
+        }
+    }
+
+    fn can_reach(&self, piece: Piece, from: Square, to: Square, capture: bool) -> bool {
+        if from == to {
+            return false;
+        }
+        let df = to.file() as i8 - from.file() as i8;
+        let dr = to.rank() as i8 - from.rank() as i8;
+
+        match piece {
+            Piece::Knight => matches!((df.abs(), dr.abs()), (1, 2) | (2, 1)),
+            Piece::King => df.abs() <= 1 && dr.abs() <= 1,
+            Piece::Bishop => df.abs() == dr.abs() && self.path_clear(from, to),
+            Piece::Rook => (df == 0 || dr == 0) && self.path_clear(from, to),
+            Piece::Queen => {
+                (df == 0 || dr == 0 || df.abs() == dr.abs()) && self.path_clear(from, to)
+            }
+            Piece::Pawn => self.pawn_can_reach(from, to, capture),
+        }
+    }
+
+    fn path_clear(&self, from: Square, to: Square) -> bool {
+        let df = (to.file() as i8 - from.file() as i8).signum();
+        let dr = (to.rank() as i8 - from.rank() as i8).signum();
+
+        let mut file = from.file() as i8 + df;
+        let mut rank = from.rank() as i8 + dr;
+        while (file, rank) != (to.file() as i8, to.rank() as i8) {
+            if self.board[Square::new(file as u8, rank as u8).0 as usize].is_some() {
+                return false;
+            }
+            file += df;
+            rank += dr;
+        }
+        true
+    }
+
+    fn pawn_can_reach(&self, from: Square, to: Square, capture: bool) -> bool {
+        let direction: i8 = match self.side_to_move {
+            Color::White => 1,
+            Color::Black => -1,
+        };
+        let df = to.file() as i8 - from.file() as i8;
+        let dr = to.rank() as i8 - from.rank() as i8;
+
+        if capture {
+            dr == direction && df.abs() == 1
+        } else if df != 0 {
+            false
+        } else if dr == direction {
+            true
+        } else {
+            let start_rank = match self.side_to_move {
+                Color::White => 1,
+                Color::Black => 6,
+            };
+            let one_step = Square::new(from.file(), (from.rank() as i8 + direction) as u8);
+            dr == 2 * direction
+                && from.rank() == start_rank
+                && self.board[one_step.0 as usize].is_none()
+        }
+    }
+
+    fn make_move(
+        &mut self,
+        source: Square,
+        dest: Square,
+        piece: Piece,
+        promotion: Option<Piece>,
+        capture: bool,
+    ) {
+        let moving = self.board[source.0 as usize].take();
+
+        // A pawn capturing into an empty square is only legal en passant.
+        if piece == Piece::Pawn && capture && self.board[dest.0 as usize].is_none() {
+            let captured_rank = match self.side_to_move {
+                Color::White => dest.rank() - 1,
+                Color::Black => dest.rank() + 1,
+            };
+            self.board[Square::new(dest.file(), captured_rank).0 as usize] = None;
+        }
+
+        let placed_piece = promotion.unwrap_or(piece);
+        self.board[dest.0 as usize] = moving.map(|(color, _)| (color, placed_piece));
+
+        self.update_castling_rights(source, dest);
+
+        self.en_passant = if piece == Piece::Pawn && (dest.rank() as i8 - source.rank() as i8).abs() == 2 {
+            Some(Square::new(source.file(), (source.rank() + dest.rank()) / 2))
+        } else {
+            None
+        };
+
+        self.side_to_move = self.side_to_move.opposite();
+    }
+
+    fn update_castling_rights(&mut self, source: Square, dest: Square) {
+        if source.0 == Square::new(4, 0).0 {
+            self.castling.white_kingside = false;
+            self.castling.white_queenside = false;
+        }
+        if source.0 == Square::new(4, 7).0 {
+            self.castling.black_kingside = false;
+            self.castling.black_queenside = false;
+        }
+        for square in [source.0, dest.0] {
+            if square == Square::new(0, 0).0 {
+                self.castling.white_queenside = false;
+            } else if square == Square::new(7, 0).0 {
+                self.castling.white_kingside = false;
+            } else if square == Square::new(0, 7).0 {
+                self.castling.black_queenside = false;
+            } else if square == Square::new(7, 7).0 {
+                self.castling.black_kingside = false;
+            }
+        }
+    }
+
+    fn castle(&mut self, kingside: bool) -> Result<(), BoardError> {
+        let rank = match self.side_to_move {
+            Color::White => 0,
+            Color::Black => 7,
+        };
+        let (king_from, king_to, rook_from, rook_to) = if kingside {
+            (
+                Square::new(4, rank),
+                Square::new(6, rank),
+                Square::new(7, rank),
+                Square::new(5, rank),
+            )
+        } else {
+            (
+                Square::new(4, rank),
+                Square::new(2, rank),
+                Square::new(0, rank),
+                Square::new(3, rank),
+            )
+        };
+
+        let has_right = match (self.side_to_move, kingside) {
+            (Color::White, true) => self.castling.white_kingside,
+            (Color::White, false) => self.castling.white_queenside,
+            (Color::Black, true) => self.castling.black_kingside,
+            (Color::Black, false) => self.castling.black_queenside,
+        };
+        if !has_right || !self.path_clear(king_from, rook_from) {
+            return Err(BoardError::IllegalMove("castling is not allowed here".to_string()));
+        }
+
+        let king = self.board[king_from.0 as usize]
+            .take()
+            .ok_or_else(|| BoardError::IllegalMove("no king to castle".to_string()))?;
+        let rook = self.board[rook_from.0 as usize]
+            .take()
+            .ok_or_else(|| BoardError::IllegalMove("no rook to castle".to_string()))?;
+
+        self.board[king_to.0 as usize] = Some(king);
+        self.board[rook_to.0 as usize] = Some(rook);
+
+        match self.side_to_move {
+            Color::White => {
+                self.castling.white_kingside = false;
+                self.castling.white_queenside = false;
+            }
+            Color::Black => {
+                self.castling.black_kingside = false;
+                self.castling.black_queenside = false;
+            }
+        }
+
+        self.en_passant = None;
+        self.side_to_move = self.side_to_move.opposite();
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn should_round_trip_the_standard_starting_fen() {
+        assert_eq!(Position::start().to_fen(), STARTPOS_FEN);
+    }
+
+    #[test]
+    fn should_apply_simple_pawn_and_knight_moves() {
+        let mut position = Position::start();
+        position.apply_san("e4").unwrap();
+        position.apply_san("e5").unwrap();
+        position.apply_san("Nf3").unwrap();
+
+        assert_eq!(
+            position.to_fen(),
+            "rnbqkbnr/pppp1ppp/8/4p3/4P3/5N2/PPPP1PPP/RNBQKB1R b KQkq - 0 1"
+        );
+    }
+
+    #[test]
+    fn should_disambiguate_by_file_when_two_pieces_can_reach_the_same_square() {
+        let mut position = Position::from_fen("8/8/8/8/8/8/4K3/R6R w - - 0 1").unwrap();
+        position.apply_san("Rad1").unwrap();
+
+        assert_eq!(position.to_fen(), "8/8/8/8/8/8/4K3/3R3R b - - 0 1");
+    }
+
+    #[test]
+    fn should_castle_kingside() {
+        let mut position = Position::from_fen("8/8/8/8/8/8/8/R3K2R w KQ - 0 1").unwrap();
+        position.apply_san("O-O").unwrap();
+
+        assert_eq!(position.to_fen(), "8/8/8/8/8/8/8/R4RK1 b - - 0 1");
+    }
+
+    #[test]
+    fn should_capture_en_passant() {
+        let mut position = Position::from_fen("8/8/8/8/pP6/8/8/8 b - b3 0 1").unwrap();
+        position.apply_san("axb3").unwrap();
+
+        assert_eq!(position.to_fen(), "8/8/8/8/8/1p6/8/8 w - - 0 1");
+    }
+
+    #[test]
+    fn should_promote_a_pawn() {
+        let mut position = Position::from_fen("8/P7/8/8/8/8/8/8 w - - 0 1").unwrap();
+        position.apply_san("a8=Q").unwrap();
+
+        assert_eq!(position.to_fen(), "Q7/8/8/8/8/8/8/8 b - - 0 1");
+    }
+}
@@ -1,9 +1,8 @@
 mod cli;
-mod pgn;
 
 use clap::Parser;
 use cli::Args;
-use pgn::Pgn;
+use pgn::pgn::Pgn;
 
 fn main() -> Result<(), Box<dyn std::error::Error>> {
     let args = Args::parse();
@@ -14,5 +13,16 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
         println!("{:?}", line);
     }
 
+    if let Some(result) = pgn.result() {
+        println!("{result:?}");
+    }
+
+    for position in pgn.positions() {
+        match position {
+            Ok(position) => println!("{}", position.to_fen()),
+            Err(e) => eprintln!("{e}"),
+        }
+    }
+
     Ok(())
 }
@@ -1,16 +1,39 @@
 use std::{collections::VecDeque, fs::File, io::BufRead, io::BufReader, path::Path};
 
 use chrono::{NaiveDate, NaiveTime};
+use nom::{
+    branch::alt,
+    bytes::complete::{tag, take_till, take_until, take_while1},
+    character::complete::{char, digit1, multispace0},
+    combinator::{map, map_res, opt, value},
+    multi::many0,
+    sequence::{delimited, preceded},
+    IResult,
+};
 use thiserror::Error;
 
+use crate::board::Position;
+
 #[derive(Debug, Hash, PartialEq, Eq, Error)]
 pub enum TagError {
     #[error("tag must start with '['")]
     NoOpeningSquareBracket,
     #[error("tag must end with ']'")]
     NoClosingSquareBracket,
-    #[error("unknown tag {0}")]
-    UnknownTag(String, String),
+    #[error("tag has no key/value separator: {0}")]
+    MalformedHeader(String),
+    #[error("malformed value for tag {0}: {1}")]
+    MalformedValue(String, String),
+}
+
+#[derive(Debug, Error)]
+pub enum PgnError {
+    #[error(transparent)]
+    Io(#[from] std::io::Error),
+    #[error("failed to parse movetext: {0}")]
+    InvalidMovetext(String),
+    #[error("illegal move: {0}")]
+    IllegalMove(String),
 }
 
 #[derive(Debug, Hash, PartialEq, Eq)]
@@ -21,7 +44,7 @@ pub enum Tag {
     Round(String),
     White(String),
     Black(String),
-    Result(String),
+    Result(GameResult),
     UTCDate(NaiveDate),
     Eco(String),
     WhiteElo(u16),
@@ -35,12 +58,144 @@ pub enum Tag {
     Termination(String),
     EndTime(NaiveTime),
     UTCTime(NaiveTime),
+    /// A well-formed `[Key "Value"]` header this crate doesn't have a
+    /// dedicated variant for (`FEN`, `SetUp`, `PlyCount`, engine-specific
+    /// keys, ...), kept around verbatim so it round-trips.
+    Other { key: String, value: String },
+}
+
+/// Why a decisive game ended: mirrors the PGN `Termination` tag. There's no
+/// dedicated "resigned" termination text in the wild (Lichess and chess.com
+/// both write `"Normal"` for a resignation exactly as they do for a played-
+/// out mate), so that case falls under `Unknown` rather than a `Resignation`
+/// variant we could never actually produce.
+#[derive(Debug, Clone, Copy, Hash, PartialEq, Eq)]
+pub enum Win {
+    Checkmate,
+    Time,
+    Forfeit,
+    Abandoned,
+    Unknown,
+}
+
+/// The typed outcome of a game, parsed from the `Result` tag's `1-0` /
+/// `0-1` / `1/2-1/2` / `*` token.
+#[derive(Debug, Clone, Copy, Hash, PartialEq, Eq)]
+pub enum GameResult {
+    White(Win),
+    Black(Win),
+    Draw,
+    Ongoing,
+}
+
+impl TryFrom<&str> for GameResult {
+    type Error = TagError;
+
+    fn try_from(value: &str) -> Result<Self, Self::Error> {
+        match value {
+            "1-0" => Ok(GameResult::White(Win::Unknown)),
+            "0-1" => Ok(GameResult::Black(Win::Unknown)),
+            "1/2-1/2" => Ok(GameResult::Draw),
+            "*" => Ok(GameResult::Ongoing),
+            other => Err(TagError::MalformedValue("Result".to_string(), other.to_string())),
+        }
+    }
+}
+
+/// Maps a checkmating last move (and, failing that, the free-text
+/// `Termination` tag) to a [`Win`] reason. `"Normal"` covers checkmate, resignation
+/// and agreed draws alike, so it only tells us anything once we've already
+/// ruled out checkmate via the last move's SAN.
+fn win_reason(termination: Option<&str>, last_san: Option<&str>) -> Win {
+    if last_san.is_some_and(|san| san.ends_with('#')) {
+        return Win::Checkmate;
+    }
+
+    match termination {
+        Some("Time forfeit") => Win::Time,
+        Some("Abandoned") => Win::Abandoned,
+        Some("Rules infraction") => Win::Forfeit,
+        _ => Win::Unknown,
+    }
+}
+
+/// Side to move, used both to tag a [`Move`] and to track whose turn it is
+/// while walking a movetext line.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Color {
+    White,
+    Black,
+}
+
+impl Color {
+    pub(crate) fn opposite(self) -> Self {
+        match self {
+            Color::White => Color::Black,
+            Color::Black => Color::White,
+        }
+    }
+}
+
+/// A human or engine judgement of a move, decoded from either its inline
+/// suffix glyph (`!`, `?`, ...) or the equivalent NAG (`$1`, `$2`, ...).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Annotation {
+    GoodMove,
+    Mistake,
+    Blunder,
+    Interesting,
+    Dubious,
+}
+
+impl Annotation {
+    fn from_glyph(glyph: &str) -> Option<Self> {
+        match glyph {
+            "!" | "!!" => Some(Annotation::GoodMove),
+            "?" => Some(Annotation::Mistake),
+            "??" => Some(Annotation::Blunder),
+            "!?" => Some(Annotation::Interesting),
+            "?!" => Some(Annotation::Dubious),
+            _ => None,
+        }
+    }
+
+    fn from_nag(nag: u8) -> Option<Self> {
+        match nag {
+            1 => Some(Annotation::GoodMove),
+            2 => Some(Annotation::Mistake),
+            3 => Some(Annotation::GoodMove),
+            4 => Some(Annotation::Blunder),
+            5 => Some(Annotation::Interesting),
+            6 => Some(Annotation::Dubious),
+            _ => None,
+        }
+    }
 }
 
+/// A single ply in the movetext, plus any Recursive Annotated Variations
+/// that branch off of it. A variation is itself a line of `Move`s, so the
+/// tree is represented as a `Move` holding a `Vec` of alternative lines.
 #[derive(Debug, PartialEq, Eq)]
-pub enum Move {
-    Black(String),
-    White(String),
+pub struct Move {
+    pub color: Color,
+    pub san: String,
+    pub variations: Vec<VecDeque<Move>>,
+    pub comment: Option<String>,
+    pub nags: Vec<u8>,
+    pub annotation: Option<Annotation>,
+}
+
+impl Move {
+    fn new(color: Color, san: &str) -> Self {
+        Self {
+            color,
+            san: san.to_string(),
+            variations: Vec::new(),
+            comment: None,
+            nags: Vec::new(),
+            annotation: None,
+        }
+    }
 }
 
 #[derive(Debug)]
@@ -50,21 +205,314 @@ pub struct Pgn {
 }
 
 impl Pgn {
-    pub fn new<P>(path: P) -> std::io::Result<Self>
+    pub fn new<P>(path: P) -> Result<Self, PgnError>
     where
         P: AsRef<Path>,
     {
         let file = File::open(path)?;
-        let lines: Vec<String> = BufReader::new(file).lines().flatten().collect();
+        let lines: Vec<String> = BufReader::new(file).lines().map_while(Result::ok).collect();
+        Self::from_lines(&lines)
+    }
+
+    fn from_lines(lines: &[String]) -> Result<Self, PgnError> {
         Ok(Self {
-            tags: parse_tags(&lines),
-            moves: parse_moves(&lines),
+            tags: parse_tags(lines),
+            moves: parse_moves(lines)?,
         })
     }
 
     pub fn tags(&self) -> &[Result<Tag, TagError>] {
         self.tags.as_ref()
     }
+
+    pub fn moves(&self) -> &VecDeque<Move> {
+        &self.moves
+    }
+
+    /// The position after each mainline ply, starting from the `FEN`/`SetUp`
+    /// tags (or the standard starting position) and applying each `Move`'s
+    /// SAN in turn. Replayed lazily on each call rather than stored on
+    /// `Pgn`, since the board model only tracks piece geometry (no pin/check
+    /// detection): a SAN that's unambiguous in a real game but ambiguous to
+    /// our replay yields an `Err` for that ply without invalidating the
+    /// tags or movetext already parsed.
+    pub fn positions(&self) -> impl Iterator<Item = Result<Position, PgnError>> + '_ {
+        let mut position = starting_position(&self.tags);
+        self.moves.iter().map(move |chess_move| {
+            position
+                .apply_san(&chess_move.san)
+                .map_err(|e| PgnError::IllegalMove(format!("{}: {e}", chess_move.san)))?;
+            Ok(position.clone())
+        })
+    }
+
+    /// The typed outcome of the game, with the `Win` reason filled in from
+    /// the last move's SAN for checkmate (falling back to the `Termination`
+    /// tag), rather than the raw `Result`/`Termination` strings.
+    pub fn result(&self) -> Option<GameResult> {
+        let result = self.tags.iter().find_map(|tag| match tag {
+            Ok(Tag::Result(result)) => Some(*result),
+            _ => None,
+        })?;
+
+        let termination = self.tags.iter().find_map(|tag| match tag {
+            Ok(Tag::Termination(termination)) => Some(termination.as_str()),
+            _ => None,
+        });
+        let last_san = self.moves.back().map(|m| m.san.as_str());
+        let reason = win_reason(termination, last_san);
+
+        Some(match result {
+            GameResult::White(_) => GameResult::White(reason),
+            GameResult::Black(_) => GameResult::Black(reason),
+            other => other,
+        })
+    }
+
+    /// Serializes this game back to valid PGN text: the Seven Tag Roster
+    /// in canonical order, then any remaining tags, a blank line, and the
+    /// movetext re-built from the variation tree, wrapped at 80 columns
+    /// and terminated with the game result token.
+    pub fn to_pgn_string(&self) -> String {
+        let mut out = String::new();
+
+        for tag in ordered_tags(&self.tags) {
+            out.push_str(&render_tag(tag));
+            out.push('\n');
+        }
+        out.push('\n');
+
+        let mut tokens = render_line(&self.moves, 1, Color::White);
+        let result = self
+            .tags
+            .iter()
+            .find_map(|tag| match tag {
+                Ok(Tag::Result(result)) => Some(*result),
+                _ => None,
+            })
+            .unwrap_or(GameResult::Ongoing);
+        tokens.push(render_game_result(result).to_string());
+
+        out.push_str(&wrap_at_80(&tokens));
+        out.push('\n');
+
+        out
+    }
+}
+
+impl std::fmt::Display for Pgn {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(&self.to_pgn_string())
+    }
+}
+
+/// Orders tags for serialization: the Seven Tag Roster first (Event, Site,
+/// Date, Round, White, Black, Result), then every other successfully
+/// parsed tag in the order it was read. Tags that failed to parse have no
+/// faithful text to re-emit, so they're dropped.
+fn ordered_tags(tags: &[Result<Tag, TagError>]) -> Vec<&Tag> {
+    fn roster_position(tag: &Tag) -> Option<u8> {
+        match tag {
+            Tag::Event(_) => Some(0),
+            Tag::Site(_) => Some(1),
+            Tag::Date(_) => Some(2),
+            Tag::Round(_) => Some(3),
+            Tag::White(_) => Some(4),
+            Tag::Black(_) => Some(5),
+            Tag::Result(_) => Some(6),
+            _ => None,
+        }
+    }
+
+    let ok_tags: Vec<&Tag> = tags.iter().filter_map(|tag| tag.as_ref().ok()).collect();
+
+    let mut roster: Vec<&Tag> = ok_tags
+        .iter()
+        .copied()
+        .filter(|t| roster_position(t).is_some())
+        .collect();
+    roster.sort_by_key(|t| roster_position(t).unwrap());
+
+    let rest = ok_tags.into_iter().filter(|t| roster_position(t).is_none());
+
+    roster.into_iter().chain(rest).collect()
+}
+
+fn render_tag(tag: &Tag) -> String {
+    match tag {
+        Tag::Event(v) => format!("[Event \"{v}\"]"),
+        Tag::Site(v) => format!("[Site \"{v}\"]"),
+        Tag::Date(v) => format!("[Date \"{}\"]", v.format("%Y.%m.%d")),
+        Tag::Round(v) => format!("[Round \"{v}\"]"),
+        Tag::White(v) => format!("[White \"{v}\"]"),
+        Tag::Black(v) => format!("[Black \"{v}\"]"),
+        Tag::Result(v) => format!("[Result \"{}\"]", render_game_result(*v)),
+        Tag::UTCDate(v) => format!("[UTCDate \"{}\"]", v.format("%Y.%m.%d")),
+        Tag::Eco(v) => format!("[ECO \"{v}\"]"),
+        Tag::WhiteElo(v) => format!("[WhiteElo \"{v}\"]"),
+        Tag::BlackElo(v) => format!("[BlackElo \"{v}\"]"),
+        Tag::Annotator(v) => format!("[Annotator \"{v}\"]"),
+        Tag::WhiteRatingDiff(v) => format!("[WhiteRatingDiff \"{v}\"]"),
+        Tag::BlackRatingDiff(v) => format!("[BlackRatingDiff \"{v}\"]"),
+        Tag::Variant(v) => format!("[Variant \"{v}\"]"),
+        Tag::TimeControl(v) => format!("[TimeControl \"{v}\"]"),
+        Tag::Opening(v) => format!("[Opening \"{v}\"]"),
+        Tag::Termination(v) => format!("[Termination \"{v}\"]"),
+        Tag::EndTime(v) => format!("[EndTime \"{}\"]", v.format("%H:%M:%S")),
+        Tag::UTCTime(v) => format!("[UTCTime \"{}\"]", v.format("%H:%M:%S")),
+        Tag::Other { key, value } => format!("[{key} \"{value}\"]"),
+    }
+}
+
+fn render_game_result(result: GameResult) -> &'static str {
+    match result {
+        GameResult::White(_) => "1-0",
+        GameResult::Black(_) => "0-1",
+        GameResult::Draw => "1/2-1/2",
+        GameResult::Ongoing => "*",
+    }
+}
+
+fn glyph_for(annotation: Annotation) -> &'static str {
+    match annotation {
+        Annotation::GoodMove => "!",
+        Annotation::Mistake => "?",
+        Annotation::Blunder => "??",
+        Annotation::Interesting => "!?",
+        Annotation::Dubious => "?!",
+    }
+}
+
+/// Re-builds one line of movetext (mainline or a variation) as a flat list
+/// of tokens: move numbers, SAN with any suffix glyph, NAGs, comments, and
+/// parenthesized variations. `start_no`/`start_color` are the move number
+/// and side to move of this line's first ply; `start_color` mirrors the
+/// side-to-move state `parse_line` threads through while reading.
+fn render_line(moves: &VecDeque<Move>, start_no: u32, start_color: Color) -> Vec<String> {
+    let mut tokens = Vec::new();
+    let mut move_no = start_no;
+    let mut color = start_color;
+    let mut first = true;
+
+    for chess_move in moves {
+        match color {
+            Color::White => tokens.push(format!("{move_no}.")),
+            Color::Black if first => tokens.push(format!("{move_no}...")),
+            Color::Black => {}
+        }
+
+        let mut san = chess_move.san.clone();
+        if let Some(annotation) = chess_move.annotation {
+            san.push_str(glyph_for(annotation));
+        }
+        tokens.push(san);
+
+        for nag in &chess_move.nags {
+            tokens.push(format!("${nag}"));
+        }
+
+        if let Some(comment) = &chess_move.comment {
+            tokens.push(format!("{{{comment}}}"));
+        }
+
+        for variation in &chess_move.variations {
+            let inner = render_line(variation, move_no, color);
+            tokens.push(format!("({})", inner.join(" ")));
+        }
+
+        if color == Color::Black {
+            move_no += 1;
+        }
+        color = color.opposite();
+        first = false;
+    }
+
+    tokens
+}
+
+/// Joins movetext tokens with spaces, wrapping to a new line once the
+/// current one would exceed 80 columns, per PGN convention.
+fn wrap_at_80(tokens: &[String]) -> String {
+    let mut lines = Vec::new();
+    let mut current = String::new();
+
+    for token in tokens {
+        if current.is_empty() {
+            current.push_str(token);
+        } else if current.len() + 1 + token.len() <= 80 {
+            current.push(' ');
+            current.push_str(token);
+        } else {
+            lines.push(std::mem::take(&mut current));
+            current.push_str(token);
+        }
+    }
+    if !current.is_empty() {
+        lines.push(current);
+    }
+
+    lines.join("\n")
+}
+
+/// A multi-game PGN file (e.g. a Lichess or chess.com export), read one
+/// game at a time rather than buffered into memory wholesale. Games are
+/// separated by blank lines and each starts with an `[Event ...]` tag.
+pub struct PgnDatabase {
+    reader: BufReader<File>,
+    /// The `[Event ...]` line of the next game, already read off the
+    /// stream while looking for the end of the previous one.
+    pending_event: Option<String>,
+}
+
+impl PgnDatabase {
+    pub fn open<P>(path: P) -> std::io::Result<Self>
+    where
+        P: AsRef<Path>,
+    {
+        let file = File::open(path)?;
+        Ok(Self {
+            reader: BufReader::new(file),
+            pending_event: None,
+        })
+    }
+}
+
+impl Iterator for PgnDatabase {
+    type Item = Result<Pgn, PgnError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let mut lines: Vec<String> = self.pending_event.take().into_iter().collect();
+        let mut seen_movetext = false;
+
+        loop {
+            let mut raw = String::new();
+            match self.reader.read_line(&mut raw) {
+                Ok(0) => break,
+                Ok(_) => {
+                    let line = raw.trim_end_matches(['\r', '\n']).to_string();
+                    let is_tag = line.starts_with('[');
+
+                    if !is_tag && !line.is_empty() {
+                        seen_movetext = true;
+                    }
+
+                    if line.starts_with("[Event ") && seen_movetext {
+                        self.pending_event = Some(line);
+                        break;
+                    }
+
+                    lines.push(line);
+                }
+                Err(e) => return Some(Err(e.into())),
+            }
+        }
+
+        if lines.iter().all(|line| line.trim().is_empty()) {
+            return None;
+        }
+
+        Some(Pgn::from_lines(&lines))
+    }
 }
 
 impl TryFrom<&str> for Tag {
@@ -79,54 +527,61 @@ impl TryFrom<&str> for Tag {
             return Err(TagError::NoClosingSquareBracket);
         };
 
-        let value = value.replace('[', "").replace(']', "");
+        let value = value.replace(['[', ']'], "");
 
-        let data = value.split_once(' ').unwrap();
+        let data = value
+            .split_once(' ')
+            .ok_or_else(|| TagError::MalformedHeader(value.clone()))?;
 
         match (data.0, data.1.replace('\"', "")) {
             ("Event", event) => Ok(Tag::Event(event)),
             ("Site", site) => Ok(Tag::Site(site)),
-            ("Date", date) => Ok(Tag::Date(
-                NaiveDate::parse_from_str(&date, "%Y.%m.%d").unwrap(),
-            )),
-            ("UTCDate", utc_date) => Ok(Tag::UTCDate(
-                NaiveDate::parse_from_str(&utc_date, "%Y.%m.%d").unwrap(),
-            )),
+            ("Date", date) => NaiveDate::parse_from_str(&date, "%Y.%m.%d")
+                .map(Tag::Date)
+                .map_err(|_| TagError::MalformedValue("Date".to_string(), date)),
+            ("UTCDate", utc_date) => NaiveDate::parse_from_str(&utc_date, "%Y.%m.%d")
+                .map(Tag::UTCDate)
+                .map_err(|_| TagError::MalformedValue("UTCDate".to_string(), utc_date)),
             ("Round", round) => Ok(Tag::Round(round)),
             ("White", white) => Ok(Tag::White(white)),
             ("Black", black) => Ok(Tag::Black(black)),
-            ("Result", result) => Ok(Tag::Result(result)),
-            ("WhiteElo", white_elo) => Ok(Tag::WhiteElo(white_elo.parse().unwrap())),
-            ("BlackElo", black_elo) => Ok(Tag::BlackElo(black_elo.parse().unwrap())),
+            ("Result", result) => Ok(Tag::Result(GameResult::try_from(result.as_str())?)),
+            ("WhiteElo", white_elo) => white_elo
+                .parse()
+                .map(Tag::WhiteElo)
+                .map_err(|_| TagError::MalformedValue("WhiteElo".to_string(), white_elo)),
+            ("BlackElo", black_elo) => black_elo
+                .parse()
+                .map(Tag::BlackElo)
+                .map_err(|_| TagError::MalformedValue("BlackElo".to_string(), black_elo)),
             ("ECO", eco) => Ok(Tag::Eco(eco)),
             ("Annotator", annotator) => Ok(Tag::Annotator(annotator)),
-            ("WhiteRatingDiff", white_diff_rating) => {
-                Ok(Tag::WhiteRatingDiff(white_diff_rating.parse().unwrap()))
-            }
-            ("BlackRatingDiff", black_diff_rating) => {
-                Ok(Tag::BlackRatingDiff(black_diff_rating.parse().unwrap()))
-            }
+            ("WhiteRatingDiff", white_diff_rating) => white_diff_rating
+                .parse()
+                .map(Tag::WhiteRatingDiff)
+                .map_err(|_| {
+                    TagError::MalformedValue("WhiteRatingDiff".to_string(), white_diff_rating)
+                }),
+            ("BlackRatingDiff", black_diff_rating) => black_diff_rating
+                .parse()
+                .map(Tag::BlackRatingDiff)
+                .map_err(|_| {
+                    TagError::MalformedValue("BlackRatingDiff".to_string(), black_diff_rating)
+                }),
             ("Variant", variant) => Ok(Tag::Variant(variant)),
             ("TimeControl", time_control) => Ok(Tag::TimeControl(time_control)),
             ("Opening", opening) => Ok(Tag::Opening(opening)),
             ("Termination", termination) => Ok(Tag::Termination(termination)),
-            ("EndTime", end_time) => Ok(Tag::EndTime(
-                NaiveTime::parse_from_str(&end_time, "%H:%M:%S %Z").unwrap(),
-            )),
-            ("UTCTime", utc_time) => Ok(Tag::UTCTime(
-                NaiveTime::parse_from_str(&utc_time, "%H:%M:%S").unwrap(),
-            )),
-            (_, unknown_data) => Err(TagError::UnknownTag(data.0.to_string(), unknown_data)),
-        }
-    }
-}
-
-impl From<(&str, &str)> for Move {
-    fn from(value: (&str, &str)) -> Self {
-        let value = (value.0.parse::<u16>().unwrap(), value.1);
-        match (value.0 % 2 == 0, value.1) {
-            (true, chess_move) => Move::Black(chess_move.to_string()),
-            (false, chess_move) => Move::White(chess_move.to_string()),
+            ("EndTime", end_time) => NaiveTime::parse_from_str(&end_time, "%H:%M:%S %Z")
+                .map(Tag::EndTime)
+                .map_err(|_| TagError::MalformedValue("EndTime".to_string(), end_time)),
+            ("UTCTime", utc_time) => NaiveTime::parse_from_str(&utc_time, "%H:%M:%S")
+                .map(Tag::UTCTime)
+                .map_err(|_| TagError::MalformedValue("UTCTime".to_string(), utc_time)),
+            (key, value) => Ok(Tag::Other {
+                key: key.to_string(),
+                value,
+            }),
         }
     }
 }
@@ -139,34 +594,385 @@ fn parse_tags(lines: &[String]) -> Vec<Result<Tag, TagError>> {
         .collect()
 }
 
-fn parse_moves(line: &[String]) -> VecDeque<Move> {
-    let line = line
+/// `12.` or `12...`. The `...` form marks a variation (or RAV) that resumes
+/// on Black's move, so the dots themselves tell us whose turn it is rather
+/// than us having to infer it from the surrounding line.
+fn parse_move_number(input: &str) -> IResult<&str, Color> {
+    preceded(
+        digit1,
+        alt((value(Color::Black, tag("...")), value(Color::White, tag(".")))),
+    )(input)
+}
+
+fn parse_san(input: &str) -> IResult<&str, &str> {
+    take_while1(|c: char| c.is_alphanumeric() || "-=+#".contains(c))(input)
+}
+
+fn parse_result_token(input: &str) -> IResult<&str, &str> {
+    alt((tag("1-0"), tag("0-1"), tag("1/2-1/2"), tag("*")))(input)
+}
+
+/// `{ ... }` comment. PGN brace comments cannot nest, so the text simply
+/// runs until the closing brace.
+fn parse_brace_comment(input: &str) -> IResult<&str, &str> {
+    delimited(char('{'), take_until("}"), char('}'))(input)
+}
+
+/// `; ...` comment, running to the end of the line.
+fn parse_semicolon_comment(input: &str) -> IResult<&str, &str> {
+    preceded(char(';'), take_till(|c| c == '\n'))(input)
+}
+
+/// A NAG is a single byte (`$0`-`$255` per the PGN spec); anything outside
+/// that range fails to parse here instead of silently coercing to `$0`.
+fn parse_nag(input: &str) -> IResult<&str, u8> {
+    map_res(preceded(char('$'), digit1), |digits: &str| digits.parse())(input)
+}
+
+/// Inline suffix glyph such as `!`, `?!` or `??`, tried longest-first so
+/// that e.g. `!?` isn't mistaken for a lone `!`.
+fn parse_glyph(input: &str) -> IResult<&str, &str> {
+    alt((tag("!!"), tag("??"), tag("!?"), tag("?!"), tag("!"), tag("?")))(input)
+}
+
+enum Trailer<'a> {
+    Comment(&'a str),
+    Nag(u8),
+}
+
+fn parse_trailer(input: &str) -> IResult<&str, Trailer<'_>> {
+    alt((
+        map(parse_brace_comment, Trailer::Comment),
+        map(parse_semicolon_comment, Trailer::Comment),
+        map(parse_nag, Trailer::Nag),
+    ))(input)
+}
+
+fn parse_variation(input: &str, color: Color) -> IResult<&str, VecDeque<Move>> {
+    delimited(char('('), |i| parse_line(i, color), char(')'))(input)
+}
+
+/// Anything that can trail a SAN token, in any order and any number of
+/// times: a comment, a NAG, or a parenthesized variation. PGN allows these
+/// to be mixed (e.g. a variation followed by a comment on why it wasn't
+/// played), so `parse_node` keeps consuming them until none match.
+enum NodeTail<'a> {
+    Trailer(Trailer<'a>),
+    Variation(VecDeque<Move>),
+}
+
+fn parse_node_tail(input: &str, color: Color) -> IResult<&str, NodeTail<'_>> {
+    alt((
+        map(parse_trailer, NodeTail::Trailer),
+        map(|i| parse_variation(i, color), NodeTail::Variation),
+    ))(input)
+}
+
+/// A single node of the mainline (or of a variation): an optional move
+/// number, the SAN itself, an optional suffix glyph, and then any mix of
+/// trailing comments, NAGs and variations that branch off of this exact
+/// ply.
+fn parse_node(input: &str, color: Color) -> IResult<&str, (Move, Color)> {
+    let (input, _) = multispace0(input)?;
+    let (input, number_color) = opt(parse_move_number)(input)?;
+    let color = number_color.unwrap_or(color);
+    let (input, _) = multispace0(input)?;
+    let (input, san) = parse_san(input)?;
+    let (input, glyph) = opt(parse_glyph)(input)?;
+    let (input, tails) = many0(preceded(multispace0, |i| parse_node_tail(i, color)))(input)?;
+
+    let mut chess_move = Move::new(color, san);
+
+    let mut comments = Vec::new();
+    for tail in tails {
+        match tail {
+            NodeTail::Trailer(Trailer::Comment(text)) => comments.push(text.trim().to_string()),
+            NodeTail::Trailer(Trailer::Nag(nag)) => chess_move.nags.push(nag),
+            NodeTail::Variation(variation) => chess_move.variations.push(variation),
+        }
+    }
+    if !comments.is_empty() {
+        chess_move.comment = Some(comments.join(" "));
+    }
+
+    chess_move.annotation = glyph.and_then(Annotation::from_glyph).or_else(|| {
+        chess_move
+            .nags
+            .iter()
+            .find_map(|&nag| Annotation::from_nag(nag))
+    });
+
+    Ok((input, (chess_move, color.opposite())))
+}
+
+/// Consumes a sequence of `parse_node`s until it hits the end of input, a
+/// closing `)` (the end of the enclosing variation), or the game result
+/// token, none of which belong to a node themselves. A comment or NAG with
+/// no SAN of its own to attach to (e.g. a pre-game annotation before the
+/// first move) is skipped rather than failing the parse, since the `Move`
+/// tree has no slot for a line-level comment.
+fn parse_line(mut input: &str, mut color: Color) -> IResult<&str, VecDeque<Move>> {
+    let mut moves = VecDeque::new();
+    loop {
+        let (rest, _) = multispace0(input)?;
+        input = rest;
+
+        if input.is_empty() || input.starts_with(')') || parse_result_token(input).is_ok() {
+            break;
+        }
+
+        if let Ok((rest, _)) = parse_trailer(input) {
+            input = rest;
+            continue;
+        }
+
+        let (rest, (chess_move, next_color)) = parse_node(input, color)?;
+        moves.push_back(chess_move);
+        color = next_color;
+        input = rest;
+    }
+    Ok((input, moves))
+}
+
+fn parse_moves(lines: &[String]) -> Result<VecDeque<Move>, PgnError> {
+    let movetext = lines
         .iter()
         .filter(|line| !line.starts_with('[') && !line.is_empty())
         .map(|l| l.as_ref())
         .collect::<Vec<&str>>()
-        .join(" ");
+        .join("\n");
 
-    let moves = line.split(pat)
+    let (_, moves) = parse_line(&movetext, Color::White)
+        .map_err(|e| PgnError::InvalidMovetext(e.to_string()))?;
 
-    println!("{}", line);
+    Ok(moves)
+}
 
-    VecDeque::new()
+/// The position the game starts from: the `FEN` tag's value if that tag is
+/// present and parses, otherwise the standard starting position. The
+/// `SetUp` tag is not consulted.
+fn starting_position(tags: &[Result<Tag, TagError>]) -> Position {
+    tags.iter()
+        .find_map(|tag| match tag {
+            Ok(Tag::Other { key, value }) if key == "FEN" => Position::from_fen(value).ok(),
+            _ => None,
+        })
+        .unwrap_or_else(Position::start)
 }
+
 #[cfg(test)]
 mod tests {
     use super::*;
 
     #[test]
-    fn should_properly_assing_move_to_move() {
-        let first_chess_move = ("1", "e4");
-        let second_chess_move = ("2", "c6");
-        let third_chess_move = ("3", "Nf3");
-        let fourth_chess_move = ("4", "exd5");
+    fn should_parse_a_flat_mainline() {
+        let lines = vec!["1. e4 c6 2. Nf3 d5 3. exd5 1-0".to_string()];
+        let moves = parse_moves(&lines).unwrap();
+
+        assert_eq!(moves.len(), 5);
+        assert_eq!(moves[0], Move::new(Color::White, "e4"));
+        assert_eq!(moves[1], Move::new(Color::Black, "c6"));
+        assert_eq!(moves[4], Move::new(Color::White, "exd5"));
+    }
+
+    #[test]
+    fn should_attach_a_variation_to_the_move_it_replaces() {
+        let lines = vec!["1. e4 e5 (1... c5 2. Nf3) 2. Nf3 1/2-1/2".to_string()];
+        let moves = parse_moves(&lines).unwrap();
+
+        assert_eq!(moves.len(), 3);
+        let e5 = &moves[1];
+        assert_eq!(e5.color, Color::Black);
+        assert_eq!(e5.variations.len(), 1);
+
+        let variation = &e5.variations[0];
+        assert_eq!(variation.len(), 2);
+        assert_eq!(variation[0], Move::new(Color::Black, "c5"));
+        assert_eq!(variation[1], Move::new(Color::White, "Nf3"));
+    }
+
+    #[test]
+    fn should_skip_a_standalone_comment_that_precedes_the_first_move() {
+        let lines = vec!["{ a Lichess study comment } 1. e4 e5 *".to_string()];
+        let moves = parse_moves(&lines).unwrap();
+
+        assert_eq!(moves.len(), 2);
+        assert_eq!(moves[0].san, "e4");
+        assert_eq!(moves[0].comment, None);
+    }
+
+    #[test]
+    fn should_attach_a_comment_that_follows_a_variation() {
+        let lines = vec!["1. e4 e5 2. Nf3 (2. d4) { a note on the alternative } 2... Nc6 *".to_string()];
+        let moves = parse_moves(&lines).unwrap();
+
+        let nf3 = &moves[2];
+        assert_eq!(nf3.san, "Nf3");
+        assert_eq!(nf3.variations.len(), 1);
+        assert_eq!(nf3.comment.as_deref(), Some("a note on the alternative"));
+    }
+
+    #[test]
+    fn should_keep_comments_and_nags_off_the_next_move() {
+        let lines = vec!["1. e4 {good move} $1 e5 *".to_string()];
+        let moves = parse_moves(&lines).unwrap();
+
+        assert_eq!(moves.len(), 2);
+        assert_eq!(moves[0].san, "e4");
+        assert_eq!(moves[1], Move::new(Color::Black, "e5"));
+    }
+
+    #[test]
+    fn should_attach_brace_comments_and_nags_to_the_move() {
+        let lines = vec!["1. e4 {best by test} $1 e5 *".to_string()];
+        let moves = parse_moves(&lines).unwrap();
+
+        assert_eq!(moves[0].comment.as_deref(), Some("best by test"));
+        assert_eq!(moves[0].nags, vec![1]);
+        assert_eq!(moves[0].annotation, Some(Annotation::GoodMove));
+    }
+
+    #[test]
+    fn should_run_semicolon_comments_to_end_of_line() {
+        let lines = vec!["1. e4 ; a fine opening move".to_string(), "e5 *".to_string()];
+        let moves = parse_moves(&lines).unwrap();
+
+        assert_eq!(moves.len(), 2);
+        assert_eq!(moves[0].comment.as_deref(), Some("a fine opening move"));
+    }
+
+    #[test]
+    fn should_decode_suffix_glyphs_into_annotations() {
+        let lines = vec!["1. e4?! e5!! *".to_string()];
+        let moves = parse_moves(&lines).unwrap();
+
+        assert_eq!(moves[0].annotation, Some(Annotation::Dubious));
+        assert_eq!(moves[1].annotation, Some(Annotation::GoodMove));
+    }
+
+    #[test]
+    fn should_reject_an_out_of_range_nag_instead_of_coercing_it_to_zero() {
+        let lines = vec!["1. e4 $300 e5 *".to_string()];
+
+        assert!(parse_moves(&lines).is_err());
+    }
+
+    #[test]
+    fn should_parse_result_tag_into_game_result() {
+        assert_eq!(GameResult::try_from("1-0").unwrap(), GameResult::White(Win::Unknown));
+        assert_eq!(GameResult::try_from("0-1").unwrap(), GameResult::Black(Win::Unknown));
+        assert_eq!(GameResult::try_from("1/2-1/2").unwrap(), GameResult::Draw);
+        assert_eq!(GameResult::try_from("*").unwrap(), GameResult::Ongoing);
+        assert!(GameResult::try_from("2-0").is_err());
+    }
+
+    #[test]
+    fn should_fill_win_reason_from_termination_tag() {
+        assert_eq!(win_reason(Some("Normal"), Some("Qxd4")), Win::Unknown);
+        assert_eq!(win_reason(Some("Normal"), Some("Qxd4#")), Win::Checkmate);
+        assert_eq!(win_reason(Some("Time forfeit"), Some("Qxd4")), Win::Time);
+        assert_eq!(win_reason(None, None), Win::Unknown);
+    }
+
+    #[test]
+    fn should_split_a_multi_game_file_into_separate_games() {
+        let path = std::env::temp_dir().join("should_split_a_multi_game_file_into_separate_games.pgn");
+        std::fs::write(
+            &path,
+            "[Event \"First\"]\n[Result \"1-0\"]\n\n1. e4 e5 1-0\n\n[Event \"Second\"]\n[Result \"0-1\"]\n\n1. d4 d5 0-1\n",
+        )
+        .unwrap();
+
+        let games: Result<Vec<Pgn>, PgnError> = PgnDatabase::open(&path).unwrap().collect();
+        std::fs::remove_file(&path).unwrap();
+        let games = games.unwrap();
+
+        assert_eq!(games.len(), 2);
+        assert_eq!(games[0].moves().len(), 2);
+        assert_eq!(games[1].moves().len(), 2);
+    }
+
+    #[test]
+    fn should_round_trip_tags_and_movetext() {
+        let lines = vec![
+            "[Event \"Test\"]".to_string(),
+            "[Site \"Earth\"]".to_string(),
+            "[Date \"2022.05.14\"]".to_string(),
+            "[Round \"1\"]".to_string(),
+            "[White \"Alice\"]".to_string(),
+            "[Black \"Bob\"]".to_string(),
+            "[Result \"1-0\"]".to_string(),
+            "".to_string(),
+            "1. e4 {good} e5 (1... c5 2. Nf3) 2. Nf3 1-0".to_string(),
+        ];
+        let pgn = Pgn::from_lines(&lines).unwrap();
+
+        let rendered = pgn.to_pgn_string();
+        let round_tripped_lines: Vec<String> = rendered.lines().map(str::to_string).collect();
+        let round_tripped = Pgn::from_lines(&round_tripped_lines).unwrap();
+
+        assert_eq!(pgn.moves(), round_tripped.moves());
+    }
+
+    #[test]
+    fn should_round_trip_a_move_carrying_both_a_glyph_and_a_nag() {
+        let lines = vec!["1. e4! $7 e5 *".to_string()];
+        let pgn = Pgn::from_lines(&lines).unwrap();
+
+        let rendered = pgn.to_pgn_string();
+        let round_tripped_lines: Vec<String> = rendered.lines().map(str::to_string).collect();
+        let round_tripped = Pgn::from_lines(&round_tripped_lines).unwrap();
+
+        assert_eq!(pgn.moves(), round_tripped.moves());
+        assert_eq!(pgn.moves()[0].annotation, Some(Annotation::GoodMove));
+        assert_eq!(pgn.moves()[0].nags, vec![7]);
+    }
+
+    #[test]
+    fn should_preserve_unrecognized_tags_as_other() {
+        let tag = Tag::try_from("[FEN \"rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w KQkq - 0 1\"]").unwrap();
+
+        assert_eq!(
+            tag,
+            Tag::Other {
+                key: "FEN".to_string(),
+                value: "rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w KQkq - 0 1".to_string(),
+            }
+        );
+    }
+
+    #[test]
+    fn should_return_malformed_value_instead_of_panicking() {
+        let err = Tag::try_from("[WhiteElo \"not-a-number\"]").unwrap_err();
+
+        assert_eq!(
+            err,
+            TagError::MalformedValue("WhiteElo".to_string(), "not-a-number".to_string())
+        );
+    }
+
+    #[test]
+    fn should_return_malformed_header_instead_of_panicking_on_a_valueless_tag() {
+        let err = Tag::try_from("[Foo]").unwrap_err();
+
+        assert_eq!(err, TagError::MalformedHeader("Foo".to_string()));
+    }
+
+    #[test]
+    fn should_degrade_a_single_ply_instead_of_failing_the_whole_parse() {
+        // The d2 knight is pinned to the d1 king by the rook on d8, so in a
+        // real game Ne4 is unambiguous — but our board model only checks
+        // piece geometry, so it (correctly, for its scope) sees two
+        // knights that can reach e4 and can't disambiguate between them.
+        let lines = vec![
+            "[FEN \"3r3k/8/8/8/8/8/3N1N2/3K4 w - - 0 1\"]".to_string(),
+            "[SetUp \"1\"]".to_string(),
+            "".to_string(),
+            "1. Ne4 *".to_string(),
+        ];
+        let pgn = Pgn::from_lines(&lines).unwrap();
 
-        assert_eq!(Move::from(first_chess_move), Move::White("e4".to_string()));
-        assert_eq!(Move::from(second_chess_move), Move::Black("c6".to_string()));
-        assert_eq!(Move::from(third_chess_move), Move::White("Nf3".to_string()));
-        assert_eq!(Move::from(fourth_chess_move), Move::Black("exd5".to_string()));
+        assert_eq!(pgn.moves().len(), 1);
+        assert!(pgn.positions().next().unwrap().is_err());
     }
 }